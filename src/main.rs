@@ -7,7 +7,13 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::net::{UnixListener, UnixStream};
+use tokio::net::{UdpSocket, UnixListener, UnixStream};
+
+mod mqtt;
+mod palette;
+mod scene;
+
+use palette::Palette;
 
 #[derive(Parser)]
 #[command(name = "pm-licht")]
@@ -36,10 +42,52 @@ struct Cli {
 
     #[arg(long, default_value_t = false)]
     big_leds: bool,
+
+    /// UDP port for WLED-compatible realtime packets (WARLS/DRGB/DNRGB)
+    #[arg(long, default_value_t = 21324)]
+    wled_port: u16,
+
+    /// MQTT broker host; enables the Home Assistant JSON light integration
+    #[arg(long)]
+    mqtt_host: Option<String>,
+
+    #[arg(long, default_value_t = 1883)]
+    mqtt_port: u16,
+
+    /// Topic prefix; commands are read from "<prefix>/set", state published to "<prefix>/state"
+    #[arg(long, default_value = "pm-licht")]
+    mqtt_topic: String,
+
+    /// Palette used by the palette-driven modes (party, lava, ocean, rainbow, candy-cane)
+    #[arg(long, default_value = "party")]
+    palette: String,
+
+    #[arg(long, default_value_t = 60)]
+    bpm: u8,
+
+    /// Path to a JSON or TOML scene file (segments + keyframes); when set, this
+    /// replaces the single-mode animation loop with the scene engine
+    #[arg(long)]
+    scene_file: Option<std::path::PathBuf>,
+
+    /// Seconds of inactivity before the strip fades to black and stops rendering; 0 disables standby.
+    /// Applies to the single-mode animation loop only; the scene engine (--scene-file) has no standby
+    #[arg(long, default_value_t = 300)]
+    standby_timeout_secs: u64,
 }
 
+const WLED_PROTOCOL_WARLS: u8 = 1;
+const WLED_PROTOCOL_DRGB: u8 = 2;
+const WLED_PROTOCOL_DNRGB: u8 = 4;
+
+const FIRE_COOLDOWN_FACTOR: f32 = 0.95;
+const FIRE_MAX_ENERGY_PROPAGATION: f32 = 0.4;
+const FIRE_EXPONENT: f32 = 1.5;
+const FIRE_W_SCALE: f32 = 0.6;
+const FIRE_W_EXPONENT: f32 = 2.2;
+
 #[derive(Clone, Copy, PartialEq, ValueEnum)]
-enum Mode {
+pub(crate) enum Mode {
     Chase,
     Flash,
     MultiChase,
@@ -48,17 +96,21 @@ enum Mode {
     FillEmpty,
     Juggle,
     Theater,
+    Fire,
+    Confetti,
+    Bpm,
+    CyclonRainbow,
 }
 
 impl Mode {
     fn random_different_from(&self) -> Self {
         let mut rng = rand::thread_rng();
-        let modes = [Mode::Chase, Mode::Flash, Mode::MultiChase, Mode::Alternate, Mode::Bounce, Mode::FillEmpty, Mode::Juggle, Mode::Theater];
+        let modes = Mode::all();
         let available: Vec<_> = modes.iter().filter(|&&m| m != *self).copied().collect();
         available[rng.gen_range(0..available.len())]
     }
-    
-    fn name(&self) -> &str {
+
+    pub(crate) fn name(&self) -> &str {
         match self {
             Mode::Chase => "Chase",
             Mode::Flash => "Flash",
@@ -68,8 +120,33 @@ impl Mode {
             Mode::FillEmpty => "FillEmpty",
             Mode::Juggle => "Juggle",
             Mode::Theater => "Theater",
+            Mode::Fire => "Fire",
+            Mode::Confetti => "Confetti",
+            Mode::Bpm => "Bpm",
+            Mode::CyclonRainbow => "CyclonRainbow",
         }
     }
+
+    pub(crate) fn all() -> &'static [Mode] {
+        &[
+            Mode::Chase,
+            Mode::Flash,
+            Mode::MultiChase,
+            Mode::Alternate,
+            Mode::Bounce,
+            Mode::FillEmpty,
+            Mode::Juggle,
+            Mode::Theater,
+            Mode::Fire,
+            Mode::Confetti,
+            Mode::Bpm,
+            Mode::CyclonRainbow,
+        ]
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Mode> {
+        Mode::all().iter().find(|m| m.name().eq_ignore_ascii_case(name)).copied()
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -78,17 +155,84 @@ struct IpcCommand {
 }
 
 #[derive(Clone)]
-struct AppState {
+pub(crate) struct AppState {
     speed: f64,
+    realtime_frame: Option<Vec<[u8; 4]>>,
+    realtime_until: Option<Instant>,
+    pub(crate) power_on: bool,
+    pub(crate) brightness: u8,
+    pub(crate) base_color: Option<[u8; 4]>,
+    pub(crate) mode_override: Option<Mode>,
+    pub(crate) flipped_override: Option<bool>,
+    pub(crate) reset_phases: bool,
+    pub(crate) palette: Palette,
+    tap_timestamps: Vec<Instant>,
+    tap_period_ms: Option<u64>,
+    pub(crate) last_activity: Instant,
+    pub(crate) forced_standby: Option<bool>,
+    pub(crate) big_leds: bool,
 }
 
+const TAP_MAX_INTERVAL: Duration = Duration::from_secs(3);
+
 impl AppState {
-    fn new() -> Self {
-        Self { speed: 1.0 }
+    fn new(brightness: u8, palette: Palette, big_leds: bool) -> Self {
+        Self {
+            speed: 1.0,
+            realtime_frame: None,
+            realtime_until: None,
+            power_on: true,
+            brightness,
+            base_color: None,
+            mode_override: None,
+            flipped_override: None,
+            reset_phases: false,
+            palette,
+            tap_timestamps: Vec::new(),
+            tap_period_ms: None,
+            last_activity: Instant::now(),
+            forced_standby: None,
+            big_leds,
+        }
     }
-    
+
     fn get_delay_ms(&self, base_delay_ms: u64) -> u64 {
-        (base_delay_ms as f64 / self.speed) as u64
+        self.tap_period_ms.unwrap_or_else(|| (base_delay_ms as f64 / self.speed) as u64)
+    }
+
+    /// Marks that an external command arrived, resetting the idle clock and
+    /// clearing any explicit standby so the strip wakes back up.
+    pub(crate) fn touch_activity(&mut self) {
+        self.last_activity = Instant::now();
+        self.forced_standby = None;
+    }
+
+    fn record_tap(&mut self) {
+        let now = Instant::now();
+
+        if let Some(&last) = self.tap_timestamps.last() {
+            let interval = now.duration_since(last);
+            if interval <= TAP_MAX_INTERVAL {
+                self.tap_period_ms = Some(interval.as_millis() as u64);
+            }
+        }
+
+        self.tap_timestamps.push(now);
+        if self.tap_timestamps.len() > 2 {
+            self.tap_timestamps.remove(0);
+        }
+    }
+
+    fn set_realtime_frame(&mut self, frame: Vec<[u8; 4]>, timeout_secs: u8) {
+        self.realtime_frame = Some(frame);
+        self.realtime_until = Some(Instant::now() + Duration::from_secs(timeout_secs.max(1) as u64));
+    }
+
+    fn active_realtime_frame(&self) -> Option<&Vec<[u8; 4]>> {
+        match self.realtime_until {
+            Some(until) if Instant::now() < until => self.realtime_frame.as_ref(),
+            _ => None,
+        }
     }
 }
 
@@ -110,7 +254,11 @@ async fn main() -> Result<()> {
         )
         .build()?;
 
-    let app_state = Arc::new(Mutex::new(AppState::new()));
+    let initial_palette = Palette::from_name(&cli.palette).unwrap_or_else(|| {
+        eprintln!("Unknown palette '{}', falling back to party", cli.palette);
+        Palette::party()
+    });
+    let app_state = Arc::new(Mutex::new(AppState::new(cli.brightness, initial_palette, cli.big_leds)));
     
     let socket_path = "/tmp/pm-licht";
     if std::path::Path::new(socket_path).exists() {
@@ -138,33 +286,235 @@ async fn main() -> Result<()> {
         }
     });
 
-    run_animation(&mut controller, cli.num_leds, cli.delay_ms, cli.mode, cli.flipped, cli.mode_duration_secs, cli.big_leds, app_state)?;
+    let wled_state = Arc::clone(&app_state);
+    let wled_port = cli.wled_port;
+    let wled_num_leds = cli.num_leds;
+    let wled_big_leds = cli.big_leds;
+    tokio::spawn(async move {
+        if let Err(e) = run_wled_listener(wled_port, wled_num_leds, wled_big_leds, wled_state).await {
+            eprintln!("WLED listener error: {}", e);
+        }
+    });
+
+    if let Some(mqtt_host) = cli.mqtt_host {
+        let mqtt_state = Arc::clone(&app_state);
+        let command_topic = format!("{}/set", cli.mqtt_topic);
+        let state_topic = format!("{}/state", cli.mqtt_topic);
+        let mqtt_port = cli.mqtt_port;
+        tokio::spawn(async move {
+            if let Err(e) = mqtt::run_mqtt_client(mqtt_host, mqtt_port, command_topic, state_topic, mqtt_state).await {
+                eprintln!("MQTT client error: {}", e);
+            }
+        });
+    }
+
+    if let Some(scene_path) = cli.scene_file {
+        let loaded_scene = scene::Scene::load_from_file(&scene_path)?;
+        run_scene(&mut controller, cli.num_leds, cli.delay_ms, loaded_scene, app_state)?;
+    } else {
+        run_animation(&mut controller, cli.num_leds, cli.delay_ms, cli.mode, cli.flipped, cli.mode_duration_secs, cli.big_leds, cli.bpm, cli.standby_timeout_secs, app_state)?;
+    }
 
     Ok(())
 }
 
+fn run_scene(controller: &mut rs_ws281x::Controller, num_leds: i32, base_delay_ms: u64, loaded_scene: scene::Scene, app_state: Arc<Mutex<AppState>>) -> Result<()> {
+    println!("Starting scene engine with {} keyframe(s)", loaded_scene.keyframes.len());
+    let mut engine = scene::SceneEngine::new(loaded_scene);
+
+    loop {
+        let (power_on, current_brightness, big_leds) = {
+            let state = app_state.lock().unwrap();
+            (state.power_on, state.brightness, state.big_leds)
+        };
+
+        controller.set_brightness(0, current_brightness);
+
+        if !power_on {
+            turn_off_leds(controller, num_leds)?;
+        } else {
+            engine.tick(controller.leds_mut(0), num_leds, big_leds);
+            controller.render()?;
+        }
+
+        let current_delay = {
+            let state = app_state.lock().unwrap();
+            state.get_delay_ms(base_delay_ms)
+        };
+
+        thread::sleep(Duration::from_millis(current_delay));
+    }
+}
+
+async fn run_wled_listener(port: u16, num_leds: i32, big_leds: bool, state: Arc<Mutex<AppState>>) -> Result<()> {
+    let socket = UdpSocket::bind(("0.0.0.0", port)).await?;
+    println!("Listening for WLED realtime packets on UDP port {}", port);
+
+    let mut buf = [0u8; 1472];
+    loop {
+        let (len, _addr) = socket.recv_from(&mut buf).await?;
+
+        let previous = {
+            let state = state.lock().unwrap();
+            state
+                .realtime_frame
+                .clone()
+                .unwrap_or_else(|| vec![[0, 0, 0, 0]; num_leds as usize])
+        };
+
+        if let Some((frame, timeout_secs)) = parse_wled_packet(&buf[..len], num_leds, &previous, big_leds) {
+            let mut state = state.lock().unwrap();
+            state.touch_activity();
+            state.set_realtime_frame(frame, timeout_secs);
+        }
+    }
+}
+
+fn parse_wled_packet(buf: &[u8], num_leds: i32, previous: &[[u8; 4]], big_leds: bool) -> Option<(Vec<[u8; 4]>, u8)> {
+    if buf.len() < 2 {
+        return None;
+    }
+
+    let protocol = buf[0];
+    let timeout_secs = buf[1];
+    let payload = &buf[2..];
+
+    let mut frame = previous.to_vec();
+    frame.resize(num_leds as usize, [0, 0, 0, 0]);
+
+    match protocol {
+        WLED_PROTOCOL_WARLS => {
+            for tuple in payload.chunks_exact(4) {
+                let index = tuple[0] as usize;
+                if index < frame.len() {
+                    // Wire order is (index, r, g, b); the strip buffer is
+                    // wired (b, g, r, w), reordered further for big LEDs.
+                    frame[index] = bgr_for_wiring([tuple[3], tuple[2], tuple[1], 0], big_leds);
+                }
+            }
+        }
+        WLED_PROTOCOL_DRGB => {
+            for (i, rgb) in payload.chunks_exact(3).enumerate() {
+                if i < frame.len() {
+                    frame[i] = bgr_for_wiring([rgb[2], rgb[1], rgb[0], 0], big_leds);
+                }
+            }
+        }
+        WLED_PROTOCOL_DNRGB => {
+            if payload.len() < 2 {
+                return None;
+            }
+            let start = ((payload[0] as usize) << 8) | payload[1] as usize;
+            for (i, rgb) in payload[2..].chunks_exact(3).enumerate() {
+                let index = start + i;
+                if index < frame.len() {
+                    frame[index] = bgr_for_wiring([rgb[2], rgb[1], rgb[0], 0], big_leds);
+                }
+            }
+        }
+        _ => return None,
+    }
+
+    Some((frame, timeout_secs))
+}
+
 async fn handle_client(stream: UnixStream, state: Arc<Mutex<AppState>>) -> Result<()> {
     let mut reader = BufReader::new(stream);
     let mut line = String::new();
-    
+
     while reader.read_line(&mut line).await? > 0 {
         if let Ok(cmd) = serde_json::from_str::<IpcCommand>(&line.trim()) {
-            if cmd.command.len() >= 3 
-                && cmd.command[0].as_str() == Some("set_property") 
-                && cmd.command[1].as_str() == Some("speed") {
-                if let Some(speed_value) = cmd.command[2].as_f64() {
-                    let mut app_state = state.lock().unwrap();
-                    app_state.speed = speed_value;
-                    println!("Speed set to: {}", speed_value);
-                }
-            }
+            dispatch_ipc_command(&cmd, &state);
         }
         line.clear();
     }
-    
+
+    Ok(())
+}
+
+fn dispatch_ipc_command(cmd: &IpcCommand, state: &Arc<Mutex<AppState>>) {
+    if cmd.command.is_empty() {
+        return;
+    }
+
+    if cmd.command[0].as_str() == Some("set_property") && cmd.command.len() >= 3 {
+        dispatch_set_property(&cmd.command[1], &cmd.command[2], state);
+    } else if cmd.command[0].as_str() == Some("tap") {
+        let mut app_state = state.lock().unwrap();
+        app_state.touch_activity();
+        app_state.record_tap();
+        println!("Tap recorded");
+    } else if cmd.command[0].as_str() == Some("sync") {
+        let mut app_state = state.lock().unwrap();
+        app_state.touch_activity();
+        app_state.reset_phases = true;
+        println!("Phase sync requested");
+    } else if cmd.command[0].as_str() == Some("wake") {
+        let mut app_state = state.lock().unwrap();
+        app_state.touch_activity();
+        println!("Wake requested");
+    } else if cmd.command[0].as_str() == Some("standby") {
+        let mut app_state = state.lock().unwrap();
+        app_state.forced_standby = Some(true);
+        println!("Standby requested");
+    }
+}
+
+fn dispatch_set_property(property: &serde_json::Value, value: &serde_json::Value, state: &Arc<Mutex<AppState>>) {
+    if property.as_str() == Some("speed") {
+        if let Some(speed_value) = value.as_f64() {
+            let mut app_state = state.lock().unwrap();
+            app_state.touch_activity();
+            app_state.speed = speed_value;
+            println!("Speed set to: {}", speed_value);
+        }
+    } else if property.as_str() == Some("mode") {
+        if let Some(mode_name) = value.as_str() {
+            if let Some(mode) = Mode::from_name(mode_name) {
+                let mut app_state = state.lock().unwrap();
+                app_state.touch_activity();
+                app_state.mode_override = Some(mode);
+                println!("Mode pinned to: {}", mode.name());
+            }
+        }
+    } else if property.as_str() == Some("flipped") {
+        if let Some(flipped) = value.as_bool() {
+            let mut app_state = state.lock().unwrap();
+            app_state.touch_activity();
+            app_state.flipped_override = Some(flipped);
+            println!("Flipped override set to: {}", flipped);
+        }
+    } else if property.as_str() == Some("palette") {
+        if let Some(palette_name) = value.as_str() {
+            if let Some(palette) = Palette::from_name(palette_name) {
+                let mut app_state = state.lock().unwrap();
+                app_state.touch_activity();
+                app_state.palette = palette;
+                println!("Palette set to: {}", palette_name);
+            }
+        }
+    }
+}
+
+fn turn_off_leds(controller: &mut rs_ws281x::Controller, num_leds: i32) -> Result<()> {
+    for i in 0..num_leds {
+        controller.leds_mut(0)[i as usize] = [0, 0, 0, 0];
+    }
+    controller.render()?;
     Ok(())
 }
 
+/// Reorders a small-LED-ordered (B, G, R, W) color for the wiring in use.
+/// Big LEDs swap the G and R channels relative to small ones (see
+/// `default_warm_white` in `run_animation`); this is its own inverse.
+pub(crate) fn bgr_for_wiring(color: [u8; 4], big_leds: bool) -> [u8; 4] {
+    if big_leds {
+        [color[0], color[2], color[1], color[3]]
+    } else {
+        color
+    }
+}
+
 fn flip_leds(leds: &mut [[u8; 4]], num_leds: i32) {
     let mut temp = vec![[0u8; 4]; num_leds as usize];
     for i in 0..num_leds {
@@ -175,17 +525,19 @@ fn flip_leds(leds: &mut [[u8; 4]], num_leds: i32) {
     }
 }
 
-fn run_animation(controller: &mut rs_ws281x::Controller, num_leds: i32, base_delay_ms: u64, initial_mode: Mode, initial_flipped: bool, mode_duration_secs: u64, big_leds: bool, app_state: Arc<Mutex<AppState>>) -> Result<()> {
+const STANDBY_FADE_DURATION_MS: f32 = 1500.0;
+
+fn run_animation(controller: &mut rs_ws281x::Controller, num_leds: i32, base_delay_ms: u64, initial_mode: Mode, initial_flipped: bool, mode_duration_secs: u64, big_leds: bool, bpm: u8, standby_timeout_secs: u64, app_state: Arc<Mutex<AppState>>) -> Result<()> {
     println!("Starting LED animation with {} mode{}", initial_mode.name(), if initial_flipped { " (flipped)" } else { "" });
-    
-    let warm_white = if big_leds {
+
+    let default_warm_white = if big_leds {
         // Big LEDs (B, R, G, W)
         [25, 255, 160, 0]
     } else {
         // Small LEDs (B, G, R, W) - cozy orange-tinted white for RGB LEDs
         [30, 170, 255, 0]
     };
-    
+
     let mut current_mode = initial_mode;
     let mut is_flipped = initial_flipped;
     let mode_duration = Duration::from_secs(mode_duration_secs);
@@ -201,58 +553,163 @@ fn run_animation(controller: &mut rs_ws281x::Controller, num_leds: i32, base_del
     let mut theater_offset = 0;
     let mut juggle_positions = [0.0f32, 0.0f32, 0.0f32];
     let mut juggle_velocities = [0.3f32, 0.5f32, 0.7f32];
-    
+    let mut fire_energy: Vec<f32> = vec![0.0; num_leds as usize];
+    let mut confetti_hue: u8 = 0;
+    let mut cyclon_position = 0;
+    let mut cyclon_direction = 1;
+    let mut cyclon_hue: u8 = 0;
+    let mut animation_start = Instant::now();
+    let standby_timeout = Duration::from_secs(standby_timeout_secs);
+    let mut standby_fade: f32 = 1.0;
+    let mut standby_asleep = false;
+    let fade_step = (base_delay_ms as f32 / STANDBY_FADE_DURATION_MS).max(0.01);
+
     loop {
-        // Check if it's time to switch modes
-        if mode_start.elapsed() >= mode_duration {
-            current_mode = current_mode.random_different_from();
-            // Randomly decide whether to flip the new mode
-            let mut rng = rand::thread_rng();
-            is_flipped = rng.gen_bool(0.5);
-            mode_start = Instant::now();
-            println!("Switching to {} mode{}", current_mode.name(), if is_flipped { " (flipped)" } else { "" });
+        let (power_on, warm_white, realtime_frame, mode_override, flipped_override, current_brightness, palette, is_idle) = {
+            let mut state = app_state.lock().unwrap();
+
+            if state.reset_phases {
+                chase_position = 0;
+                flash_state = false;
+                alternate_state = false;
+                bounce_position = 0;
+                bounce_direction = 1;
+                fill_position = 0;
+                fill_is_filling = true;
+                theater_offset = 0;
+                juggle_positions = [0.0, 0.0, 0.0];
+                juggle_velocities = [0.3, 0.5, 0.7];
+                fire_energy.iter_mut().for_each(|e| *e = 0.0);
+                confetti_hue = 0;
+                cyclon_position = 0;
+                cyclon_direction = 1;
+                cyclon_hue = 0;
+                animation_start = Instant::now();
+                mode_start = Instant::now();
+                state.reset_phases = false;
+            }
+
+            let is_idle = match state.forced_standby {
+                Some(forced) => forced,
+                None => standby_timeout > Duration::ZERO && state.last_activity.elapsed() >= standby_timeout,
+            };
+
+            (
+                state.power_on,
+                state.base_color.unwrap_or(default_warm_white),
+                state.active_realtime_frame().cloned(),
+                state.mode_override,
+                state.flipped_override,
+                state.brightness,
+                state.palette,
+                is_idle,
+            )
+        };
+
+        let fade_target = if is_idle { 0.0 } else { 1.0 };
+        if standby_fade < fade_target {
+            standby_fade = (standby_fade + fade_step).min(fade_target);
+        } else if standby_fade > fade_target {
+            standby_fade = (standby_fade - fade_step).max(fade_target);
         }
-        
-        // Run the appropriate mode
-        match current_mode {
-            Mode::Chase => {
-                run_chase_step(controller, num_leds, &mut chase_position, warm_white)?;
-            },
-            Mode::Flash => {
-                run_flash_step(controller, num_leds, &mut flash_state, warm_white)?;
-            },
-            Mode::MultiChase => {
-                run_multi_chase_step(controller, num_leds, &mut chase_position, warm_white)?;
-            },
-            Mode::Alternate => {
-                run_alternate_step(controller, num_leds, &mut alternate_state, warm_white)?;
-            },
-            Mode::Bounce => {
-                run_bounce_step(controller, num_leds, &mut bounce_position, &mut bounce_direction, warm_white)?;
-            },
-            Mode::FillEmpty => {
-                run_fill_empty_step(controller, num_leds, &mut fill_position, &mut fill_is_filling, warm_white)?;
-            },
-            Mode::Theater => {
-                run_theater_step(controller, num_leds, &mut theater_offset, warm_white)?;
-            },
-            Mode::Juggle => {
-                run_juggle_step(controller, num_leds, &mut juggle_positions, &mut juggle_velocities, warm_white)?;
-            },
+
+        if let Some(overridden) = flipped_override {
+            is_flipped = overridden;
         }
-        
-        // Apply flipping if enabled
-        if is_flipped {
-            flip_leds(controller.leds_mut(0), num_leds);
+
+        if is_idle && standby_fade <= 0.0 && standby_asleep {
+            // Fully faded to black and already rendered dark; stop rendering
+            // new frames until activity resumes, to save power.
+        } else if is_idle && standby_fade <= 0.0 {
+            // Just reached full darkness: render one black frame, then sleep.
+            controller.set_brightness(0, 0);
+            turn_off_leds(controller, num_leds)?;
+            standby_asleep = true;
+        } else if !power_on {
+            controller.set_brightness(0, current_brightness);
+            turn_off_leds(controller, num_leds)?;
+        } else {
+            standby_asleep = false;
+            controller.set_brightness(0, (current_brightness as f32 * standby_fade) as u8);
+
+            if let Some(overridden) = mode_override {
+                current_mode = overridden;
+            }
+
+            if let Some(frame) = realtime_frame {
+                // A WLED realtime packet arrived recently; render it verbatim and
+                // leave the mode timer untouched until it expires.
+                let leds = controller.leds_mut(0);
+                for i in 0..num_leds as usize {
+                    leds[i] = frame[i];
+                }
+            } else {
+                // Check if it's time to switch modes (unless an effect was pinned via MQTT/IPC)
+                if mode_override.is_none() && mode_start.elapsed() >= mode_duration {
+                    current_mode = current_mode.random_different_from();
+                    if flipped_override.is_none() {
+                        // Randomly decide whether to flip the new mode
+                        let mut rng = rand::thread_rng();
+                        is_flipped = rng.gen_bool(0.5);
+                    }
+                    mode_start = Instant::now();
+                    println!("Switching to {} mode{}", current_mode.name(), if is_flipped { " (flipped)" } else { "" });
+                }
+
+                // Run the appropriate mode
+                match current_mode {
+                    Mode::Chase => {
+                        run_chase_step(controller, num_leds, &mut chase_position, warm_white)?;
+                    },
+                    Mode::Flash => {
+                        run_flash_step(controller, num_leds, &mut flash_state, warm_white)?;
+                    },
+                    Mode::MultiChase => {
+                        run_multi_chase_step(controller, num_leds, &mut chase_position, warm_white)?;
+                    },
+                    Mode::Alternate => {
+                        run_alternate_step(controller, num_leds, &mut alternate_state, warm_white)?;
+                    },
+                    Mode::Bounce => {
+                        run_bounce_step(controller, num_leds, &mut bounce_position, &mut bounce_direction, warm_white)?;
+                    },
+                    Mode::FillEmpty => {
+                        run_fill_empty_step(controller, num_leds, &mut fill_position, &mut fill_is_filling, warm_white)?;
+                    },
+                    Mode::Theater => {
+                        run_theater_step(controller, num_leds, &mut theater_offset, warm_white)?;
+                    },
+                    Mode::Juggle => {
+                        run_juggle_step(controller, num_leds, &mut juggle_positions, &mut juggle_velocities, warm_white)?;
+                    },
+                    Mode::Fire => {
+                        run_fire_step(controller, num_leds, &mut fire_energy, big_leds)?;
+                    },
+                    Mode::Confetti => {
+                        run_confetti_step(controller, num_leds, &mut confetti_hue, &palette, big_leds)?;
+                    },
+                    Mode::Bpm => {
+                        run_bpm_step(controller, num_leds, bpm, animation_start, &palette, big_leds)?;
+                    },
+                    Mode::CyclonRainbow => {
+                        run_cyclon_rainbow_step(controller, num_leds, &mut cyclon_position, &mut cyclon_direction, &mut cyclon_hue, &palette, big_leds)?;
+                    },
+                }
+
+                // Apply flipping if enabled
+                if is_flipped {
+                    flip_leds(controller.leds_mut(0), num_leds);
+                }
+            }
+
+            controller.render()?;
         }
-        
-        controller.render()?;
-        
+
         let current_delay = {
             let state = app_state.lock().unwrap();
             state.get_delay_ms(base_delay_ms)
         };
-        
+
         thread::sleep(Duration::from_millis(current_delay));
     }
 }
@@ -424,6 +881,127 @@ fn run_juggle_step(controller: &mut rs_ws281x::Controller, num_leds: i32, positi
             controller.leds_mut(0)[led_index] = color;
         }
     }
-    
+
+    Ok(())
+}
+
+fn run_fire_step(controller: &mut rs_ws281x::Controller, num_leds: i32, energy: &mut [f32], big_leds: bool) -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let num_leds = num_leds as usize;
+
+    // Inject fuel at the base
+    let new_energy: f32 = rng.gen::<f32>() * 0.8 + 0.2;
+    energy[0] = (energy[0] + rng.gen::<f32>() * new_energy).min(1.0);
+
+    // Cool every cell so flames flicker and die down
+    for cell in energy.iter_mut() {
+        *cell = (*cell * FIRE_COOLDOWN_FACTOR - rng.gen::<f32>() * 0.02).max(0.0);
+    }
+
+    // Propagate upward: each cell pulls a fraction of the difference from its
+    // lower neighbor, iterating top-down so energy rises through the strip.
+    for i in (1..num_leds).rev() {
+        let pull = (energy[i - 1] - energy[i]) * FIRE_MAX_ENERGY_PROPAGATION;
+        energy[i] = (energy[i] + pull).clamp(0.0, 1.0);
+    }
+
+    // Bleed energy off the top cell
+    if let Some(top) = energy.last_mut() {
+        *top *= FIRE_COOLDOWN_FACTOR;
+    }
+
+    for i in 0..num_leds {
+        controller.leds_mut(0)[i] = fire_color(energy[i], big_leds);
+    }
+
+    Ok(())
+}
+
+fn fire_color(e: f32, big_leds: bool) -> [u8; 4] {
+    let e = e.clamp(0.0, 1.0);
+    let t = e.powf(FIRE_EXPONENT);
+
+    // Black -> red -> orange -> yellow ramp
+    let (r, g, b) = if t < 1.0 / 3.0 {
+        let local = t * 3.0;
+        (local, 0.0, 0.0)
+    } else if t < 2.0 / 3.0 {
+        let local = (t - 1.0 / 3.0) * 3.0;
+        (1.0, local * 0.65, 0.0)
+    } else {
+        let local = (t - 2.0 / 3.0) * 3.0;
+        (1.0, 0.65 + local * 0.35, local * 0.15)
+    };
+
+    let w = (e * FIRE_W_SCALE).powf(FIRE_W_EXPONENT);
+
+    let r = (r * 255.0) as u8;
+    let g = (g * 255.0) as u8;
+    let b = (b * 255.0) as u8;
+    let w = (w * 255.0) as u8;
+
+    if big_leds {
+        [b, r, g, w]
+    } else {
+        [b, g, r, w]
+    }
+}
+
+const CONFETTI_FADE_AMOUNT: u8 = 20;
+
+fn run_confetti_step(controller: &mut rs_ws281x::Controller, num_leds: i32, hue: &mut u8, palette: &Palette, big_leds: bool) -> Result<()> {
+    for channel in controller.leds_mut(0)[..num_leds as usize].iter_mut().flatten() {
+        *channel = channel.saturating_sub(CONFETTI_FADE_AMOUNT);
+    }
+
+    let mut rng = rand::thread_rng();
+    let index = rng.gen_range(0..num_leds) as usize;
+    let color_pos = hue.wrapping_add(rng.gen_range(0..64));
+    controller.leds_mut(0)[index] = bgr_for_wiring(palette.sample(color_pos), big_leds);
+
+    *hue = hue.wrapping_add(1);
+    Ok(())
+}
+
+fn beatsin8(bpm: u8, start: Instant) -> u8 {
+    let beats_per_sec = bpm as f32 / 60.0;
+    let phase = (start.elapsed().as_secs_f32() * beats_per_sec * std::f32::consts::TAU).sin();
+    (((phase + 1.0) / 2.0) * 255.0) as u8
+}
+
+fn scale_u8(value: u8, scale: u8) -> u8 {
+    ((value as u16 * scale as u16) / 255) as u8
+}
+
+fn run_bpm_step(controller: &mut rs_ws281x::Controller, num_leds: i32, bpm: u8, start: Instant, palette: &Palette, big_leds: bool) -> Result<()> {
+    let beat = beatsin8(bpm, start);
+
+    for i in 0..num_leds {
+        let hue = beat.wrapping_add((i as u32 * 2) as u8);
+        let color = bgr_for_wiring(palette.sample(hue), big_leds);
+        controller.leds_mut(0)[i as usize] = [
+            scale_u8(color[0], beat),
+            scale_u8(color[1], beat),
+            scale_u8(color[2], beat),
+            0,
+        ];
+    }
+
+    Ok(())
+}
+
+fn run_cyclon_rainbow_step(controller: &mut rs_ws281x::Controller, num_leds: i32, position: &mut i32, direction: &mut i32, hue: &mut u8, palette: &Palette, big_leds: bool) -> Result<()> {
+    for i in 0..num_leds {
+        controller.leds_mut(0)[i as usize] = [0, 0, 0, 0];
+    }
+
+    controller.leds_mut(0)[*position as usize] = bgr_for_wiring(palette.sample(*hue), big_leds);
+
+    *position += *direction;
+    if *position >= num_leds - 1 || *position <= 0 {
+        *direction = -*direction;
+    }
+
+    *hue = hue.wrapping_add(2);
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,108 @@
+use crate::{bgr_for_wiring, AppState, Mode};
+use anyhow::Result;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+struct HaColor {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct HaLightCommand {
+    state: Option<String>,
+    brightness: Option<u8>,
+    color: Option<HaColor>,
+    effect: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct HaLightState {
+    state: String,
+    brightness: u8,
+    color: HaColor,
+    effect: String,
+    effect_list: Vec<String>,
+}
+
+/// Subscribes to `command_topic` for Home Assistant JSON light schema
+/// commands and mirrors the resulting state back onto `state_topic`.
+pub(crate) async fn run_mqtt_client(
+    host: String,
+    port: u16,
+    command_topic: String,
+    state_topic: String,
+    state: Arc<Mutex<AppState>>,
+) -> Result<()> {
+    let mut mqtt_options = MqttOptions::new("pm-licht", host, port);
+    mqtt_options.set_keep_alive(Duration::from_secs(30));
+
+    let (client, mut event_loop) = AsyncClient::new(mqtt_options, 10);
+    client.subscribe(&command_topic, QoS::AtLeastOnce).await?;
+    publish_state(&client, &state_topic, &state).await?;
+
+    loop {
+        match event_loop.poll().await {
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                if publish.topic == command_topic {
+                    if let Ok(cmd) = serde_json::from_slice::<HaLightCommand>(&publish.payload) {
+                        apply_command(&state, cmd);
+                        publish_state(&client, &state_topic, &state).await?;
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("MQTT connection error: {}", e);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+fn apply_command(state: &Arc<Mutex<AppState>>, cmd: HaLightCommand) {
+    let mut state = state.lock().unwrap();
+    state.touch_activity();
+
+    if let Some(power) = cmd.state {
+        state.power_on = power.eq_ignore_ascii_case("ON");
+    }
+
+    if let Some(brightness) = cmd.brightness {
+        state.brightness = brightness;
+    }
+
+    if let Some(color) = cmd.color {
+        state.base_color = Some(bgr_for_wiring([color.b, color.g, color.r, 0], state.big_leds));
+    }
+
+    if let Some(effect) = cmd.effect {
+        if let Some(mode) = Mode::from_name(&effect) {
+            state.mode_override = Some(mode);
+        }
+    }
+}
+
+async fn publish_state(client: &AsyncClient, state_topic: &str, state: &Arc<Mutex<AppState>>) -> Result<()> {
+    let payload = {
+        let state = state.lock().unwrap();
+        // base_color is stored in wiring order; un-swap it back to (B, G, R, W)
+        // before picking the RGB channels out (the swap is its own inverse).
+        let color = bgr_for_wiring(state.base_color.unwrap_or([0, 0, 0, 0]), state.big_leds);
+        HaLightState {
+            state: if state.power_on { "ON".to_string() } else { "OFF".to_string() },
+            brightness: state.brightness,
+            color: HaColor { r: color[2], g: color[1], b: color[0] },
+            effect: state.mode_override.map(|m| m.name().to_string()).unwrap_or_default(),
+            effect_list: Mode::all().iter().map(|m| m.name().to_string()).collect(),
+        }
+    };
+
+    let json = serde_json::to_vec(&payload)?;
+    client.publish(state_topic, QoS::AtLeastOnce, true, json).await?;
+    Ok(())
+}
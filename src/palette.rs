@@ -0,0 +1,84 @@
+/// A 16-anchor color gradient sampled with linear interpolation, in the
+/// style of FastLED's `CRGBPalette16`.
+#[derive(Clone, Copy)]
+pub(crate) struct Palette {
+    anchors: [[u8; 3]; 16],
+}
+
+impl Palette {
+    const fn new(anchors: [[u8; 3]; 16]) -> Self {
+        Self { anchors }
+    }
+
+    pub(crate) fn sample(&self, pos: u8) -> [u8; 4] {
+        let segment_width = 255.0 / 15.0;
+        let segment = ((pos as f32 / segment_width) as usize).min(14);
+        let local = (pos as f32 - segment as f32 * segment_width) / segment_width;
+
+        let a = self.anchors[segment];
+        let b = self.anchors[segment + 1];
+        let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * local).round() as u8;
+
+        // Anchors are stored as RGB, but the strip buffer is wired small-LED
+        // order (B, G, R, W); reorder here so the result is in that order.
+        // Callers pass it through `bgr_for_wiring` for big LEDs, as
+        // `scene::color_bgrw` does for scene colors.
+        [lerp(a[2], b[2]), lerp(a[1], b[1]), lerp(a[0], b[0]), 0]
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "party" => Some(Self::party()),
+            "lava" => Some(Self::lava()),
+            "ocean" => Some(Self::ocean()),
+            "rainbow" => Some(Self::rainbow()),
+            "candy-cane" | "candy_cane" | "candycane" => Some(Self::candy_cane()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn party() -> Self {
+        Self::new([
+            [0xff, 0x00, 0x80], [0xff, 0x00, 0x00], [0xff, 0x80, 0x00], [0xff, 0xff, 0x00],
+            [0x80, 0xff, 0x00], [0x00, 0xff, 0x00], [0x00, 0xff, 0x80], [0x00, 0xff, 0xff],
+            [0x00, 0x80, 0xff], [0x00, 0x00, 0xff], [0x80, 0x00, 0xff], [0xff, 0x00, 0xff],
+            [0xff, 0x00, 0x80], [0xff, 0x00, 0x00], [0xff, 0x80, 0x00], [0xff, 0xff, 0x00],
+        ])
+    }
+
+    pub(crate) fn lava() -> Self {
+        Self::new([
+            [0x00, 0x00, 0x00], [0x10, 0x00, 0x00], [0x30, 0x00, 0x00], [0x60, 0x00, 0x00],
+            [0x90, 0x00, 0x00], [0xb0, 0x10, 0x00], [0xd0, 0x30, 0x00], [0xe0, 0x50, 0x00],
+            [0xff, 0x70, 0x00], [0xff, 0x90, 0x00], [0xff, 0xb0, 0x00], [0xff, 0xd0, 0x30],
+            [0xff, 0xe0, 0x60], [0xff, 0xf0, 0x90], [0xff, 0xff, 0xc0], [0xff, 0xff, 0xff],
+        ])
+    }
+
+    pub(crate) fn ocean() -> Self {
+        Self::new([
+            [0x00, 0x00, 0x10], [0x00, 0x00, 0x30], [0x00, 0x10, 0x50], [0x00, 0x20, 0x70],
+            [0x00, 0x30, 0x90], [0x00, 0x50, 0xa0], [0x00, 0x70, 0xb0], [0x00, 0x90, 0xc0],
+            [0x00, 0xa0, 0xd0], [0x10, 0xb0, 0xe0], [0x30, 0xc0, 0xe0], [0x50, 0xd0, 0xf0],
+            [0x70, 0xe0, 0xf0], [0x90, 0xf0, 0xff], [0xc0, 0xff, 0xff], [0xff, 0xff, 0xff],
+        ])
+    }
+
+    pub(crate) fn rainbow() -> Self {
+        Self::new([
+            [0xff, 0x00, 0x00], [0xff, 0x40, 0x00], [0xff, 0x80, 0x00], [0xff, 0xc0, 0x00],
+            [0xff, 0xff, 0x00], [0xc0, 0xff, 0x00], [0x80, 0xff, 0x00], [0x00, 0xff, 0x00],
+            [0x00, 0xff, 0x80], [0x00, 0xff, 0xff], [0x00, 0x80, 0xff], [0x00, 0x00, 0xff],
+            [0x40, 0x00, 0xff], [0x80, 0x00, 0xff], [0xc0, 0x00, 0xff], [0xff, 0x00, 0x80],
+        ])
+    }
+
+    pub(crate) fn candy_cane() -> Self {
+        Self::new([
+            [0xff, 0xff, 0xff], [0xff, 0xff, 0xff], [0xff, 0x00, 0x00], [0xff, 0x00, 0x00],
+            [0xff, 0xff, 0xff], [0xff, 0xff, 0xff], [0xff, 0x00, 0x00], [0xff, 0x00, 0x00],
+            [0xff, 0xff, 0xff], [0xff, 0xff, 0xff], [0xff, 0x00, 0x00], [0xff, 0x00, 0x00],
+            [0xff, 0xff, 0xff], [0xff, 0xff, 0xff], [0xff, 0x00, 0x00], [0xff, 0x00, 0x00],
+        ])
+    }
+}
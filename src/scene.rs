@@ -0,0 +1,182 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Segment {
+    pub(crate) name: String,
+    pub(crate) start: i32,
+    pub(crate) end: i32,
+    #[serde(default)]
+    #[allow(dead_code)]
+    pub(crate) tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Effect {
+    Off,
+    Solid { color: [u8; 3] },
+    Chase { color: [u8; 3] },
+    Breathe { color: [u8; 3] },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Layer {
+    pub(crate) segment: String,
+    pub(crate) effect: Effect,
+    #[serde(default)]
+    pub(crate) priority: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct KeyFrame {
+    pub(crate) duration_secs: u64,
+    pub(crate) layers: Vec<Layer>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Scene {
+    pub(crate) segments: Vec<Segment>,
+    pub(crate) keyframes: Vec<KeyFrame>,
+}
+
+impl Scene {
+    pub(crate) fn load_from_file(path: &Path) -> Result<Scene> {
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(toml::from_str(&contents)?),
+            _ => Ok(serde_json::from_str(&contents)?),
+        }
+    }
+
+    fn segment_range(&self, name: &str) -> Option<(i32, i32)> {
+        self.segments.iter().find(|s| s.name == name).map(|s| (s.start, s.end))
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct LayerState {
+    position: i32,
+    phase: f32,
+}
+
+/// Advances through a `Scene`'s keyframes by wall-clock time, compositing
+/// each keyframe's layers into the strip with the highest-priority
+/// non-transparent layer winning per pixel.
+pub(crate) struct SceneEngine {
+    scene: Scene,
+    keyframe_index: usize,
+    keyframe_start: Instant,
+    layer_states: Vec<LayerState>,
+}
+
+impl SceneEngine {
+    pub(crate) fn new(scene: Scene) -> Self {
+        let layer_states = Self::fresh_layer_states(&scene, 0);
+        Self {
+            scene,
+            keyframe_index: 0,
+            keyframe_start: Instant::now(),
+            layer_states,
+        }
+    }
+
+    fn fresh_layer_states(scene: &Scene, keyframe_index: usize) -> Vec<LayerState> {
+        let layer_count = scene
+            .keyframes
+            .get(keyframe_index)
+            .map(|k| k.layers.len())
+            .unwrap_or(0);
+        vec![LayerState::default(); layer_count]
+    }
+
+    fn advance_if_needed(&mut self) {
+        if self.scene.keyframes.is_empty() {
+            return;
+        }
+
+        let duration = Duration::from_secs(self.scene.keyframes[self.keyframe_index].duration_secs);
+        if self.keyframe_start.elapsed() >= duration {
+            self.keyframe_index = (self.keyframe_index + 1) % self.scene.keyframes.len();
+            self.keyframe_start = Instant::now();
+            self.layer_states = Self::fresh_layer_states(&self.scene, self.keyframe_index);
+        }
+    }
+
+    pub(crate) fn tick(&mut self, leds: &mut [[u8; 4]], num_leds: i32, big_leds: bool) {
+        if self.scene.keyframes.is_empty() {
+            return;
+        }
+
+        self.advance_if_needed();
+
+        let mut composite: Vec<Option<[u8; 4]>> = vec![None; num_leds as usize];
+
+        let mut layers: Vec<(usize, &Layer)> =
+            self.scene.keyframes[self.keyframe_index].layers.iter().enumerate().collect();
+        layers.sort_by_key(|(_, layer)| layer.priority);
+
+        for (i, layer) in layers {
+            let Some((start, end)) = self.scene.segment_range(&layer.segment) else {
+                continue;
+            };
+            let start = start.max(0);
+            let end = end.min(num_leds);
+            if end <= start {
+                continue;
+            }
+
+            let state = &mut self.layer_states[i];
+            let colors = render_effect(&layer.effect, state, (end - start) as usize, big_leds);
+            for (offset, color) in colors.into_iter().enumerate() {
+                // A fully-black pixel (e.g. an `Off` layer, or an unlit Chase
+                // cell) is transparent: leave any lower-priority layer's
+                // color in place instead of blanking it.
+                if color != [0, 0, 0, 0] {
+                    composite[start as usize + offset] = Some(color);
+                }
+            }
+        }
+
+        // Pixels that no layer lights are blanked, so nothing from the
+        // previous frame or keyframe lingers.
+        for (i, pixel) in composite.into_iter().enumerate() {
+            leds[i] = pixel.unwrap_or([0, 0, 0, 0]);
+        }
+    }
+}
+
+fn color_bgrw(color: [u8; 3], big_leds: bool) -> [u8; 4] {
+    crate::bgr_for_wiring([color[2], color[1], color[0], 0], big_leds)
+}
+
+fn render_effect(effect: &Effect, state: &mut LayerState, len: usize, big_leds: bool) -> Vec<[u8; 4]> {
+    match effect {
+        Effect::Off => vec![[0, 0, 0, 0]; len],
+        Effect::Solid { color } => vec![color_bgrw(*color, big_leds); len],
+        Effect::Chase { color } => {
+            let mut buf = vec![[0, 0, 0, 0]; len];
+            if len > 0 {
+                state.position = (state.position + 1) % len as i32;
+                buf[state.position as usize] = color_bgrw(*color, big_leds);
+            }
+            buf
+        }
+        Effect::Breathe { color } => {
+            state.phase += 0.05;
+            let brightness = (state.phase.sin() + 1.0) / 2.0;
+            let c = color_bgrw(*color, big_leds);
+            vec![
+                [
+                    (c[0] as f32 * brightness) as u8,
+                    (c[1] as f32 * brightness) as u8,
+                    (c[2] as f32 * brightness) as u8,
+                    0,
+                ];
+                len
+            ]
+        }
+    }
+}